@@ -0,0 +1,65 @@
+#[derive(Debug, Clone)]
+pub struct CrawlerConfig {
+    pub max_depth: usize,
+    pub max_pages: usize,
+    pub concurrent_requests: usize,
+    pub delay_ms: u64,
+    pub user_agent: String,
+    pub respect_robots_txt: bool,
+    /// Content types (matched as substrings of the response's `Content-Type`
+    /// header) that are worth reading and parsing.
+    pub accepted_content_types: Vec<String>,
+    /// Maximum number of redirects `reqwest` will follow per request.
+    pub max_redirect: usize,
+    /// Caps how many links from a single page are enqueued, regardless of
+    /// how many `a[href]`s it contains. `None` means unbounded.
+    pub links_per_page_budget: Option<usize>,
+    /// Treat `www.example.com` and `example.com` as the same host for
+    /// domain scoping.
+    pub allow_www: bool,
+    /// If non-empty, links discovered on crawled pages are only followed to
+    /// these hosts (after `allow_www` normalization). Seed and sitemap URLs
+    /// are not checked against this list.
+    pub domain_allowlist: Vec<String>,
+    /// Hosts whose discovered links are never followed, even if present in
+    /// `domain_allowlist`. Seed and sitemap URLs are not checked against
+    /// this list.
+    pub domain_blocklist: Vec<String>,
+    /// Caps how many URLs may sit in the frontier at once; `add_url` drops
+    /// (with a warning) any URL that arrives while the frontier is full.
+    /// `None` means unbounded.
+    pub max_queue_size: Option<usize>,
+    /// When seeding from a sitemap, skip a URL whose stored `last_fetched`
+    /// is younger than this TTL, even if the sitemap gave no `lastmod`.
+    /// `None` disables the TTL check (only explicit `lastmod` comparisons apply).
+    pub recrawl_after: Option<chrono::Duration>,
+}
+
+impl Default for CrawlerConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 2,
+            max_pages: 100,
+            concurrent_requests: 8,
+            delay_ms: 200,
+            user_agent: "RustWebCrawler/1.0 (https://example.com/bot)".to_string(),
+            respect_robots_txt: true,
+            accepted_content_types: vec!["text/html".to_string(), "text/plain".to_string()],
+            max_redirect: 5,
+            links_per_page_budget: None,
+            allow_www: true,
+            domain_allowlist: Vec::new(),
+            domain_blocklist: Vec::new(),
+            max_queue_size: None,
+            recrawl_after: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CrawlResult {
+    pub pages_processed: usize,
+    pub unique_urls: usize,
+    pub errors: usize,
+    pub elapsed_seconds: f64,
+}
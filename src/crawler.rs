@@ -1,60 +1,231 @@
 use crate::config::{CrawlResult, CrawlerConfig};
-use crate::storage::Store,
-use crate::robots::RobotsTxt,
-use crate::storage::ContentStore;
+use crate::robots::RobotsTxt;
+use crate::rules::{
+    ContentTypeFilter, DefaultDocumentParser, DefaultLinkExtractor, DocumentParser,
+    DomainScopeFilter, LinkExtractor, StatusFilter, TaskFilter,
+};
+use crate::search::SearchIndex;
+use crate::sitemap::fetch_sitemap_entries;
+use crate::storage::{ContentStore, Page};
 use crate::utils::url::normalize_url;
 
-use dashmap::DashSet;
+use chrono::Utc;
+use dashmap::{DashMap, DashSet};
 use log::{debug, error, info, warn};
+use reqwest::redirect::Policy;
 use reqwest::Client;
-use scraper::{Html, Selector};
+use scraper::Html;
 use std::collections::VecDeque;
 use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
 use tokio::time::sleep;
 use url::Url;
 
+/// A queued URL plus (when `max_queue_size` is set) the permit backing its
+/// slot in the bounded frontier; dropping the permit frees the slot.
+type QueueItem = (String, usize, Option<OwnedSemaphorePermit>);
+
 pub struct Crawler {
     client: Client,
     visited: Arc<DashSet<String>>,
-    queue: Arc<Mutex<VecDeque<(String, usize)>>>,
-    pages_crawled: Arc<Mutex<usize>>,
+    /// Pending URLs, bucketed by host so a single domain can't be hammered
+    /// by every worker at once.
+    host_queues: Arc<DashMap<String, VecDeque<QueueItem>>>,
+    /// Earliest time each host may be fetched again, per its crawl-delay.
+    host_next_allowed: Arc<DashMap<String, Instant>>,
+    /// Round-robin order of hosts with pending work.
+    host_cycle: Arc<Mutex<VecDeque<String>>>,
+    /// Hosts currently represented in `host_cycle`, so `add_url` only
+    /// re-enqueues a host once it has fallen out of rotation.
+    hosts_scheduled: Arc<DashSet<String>>,
+    /// Bounds the total number of URLs sitting in `host_queues`; `add_url`
+    /// drops (and warns about) a discovered link when the frontier is full
+    /// rather than blocking, since every caller of `add_url` is itself a
+    /// worker that would otherwise have to stop draining the queue to wait
+    /// for a slot, deadlocking the whole pool.
+    queue_permits: Option<Arc<Semaphore>>,
+    /// Caps how many pages are ever fetched: one permit is permanently
+    /// consumed per successfully crawled page.
+    page_budget: Arc<Semaphore>,
+    pages_crawled: Arc<AtomicUsize>,
+    /// Workers currently mid-fetch, so the frontier-empty check below can't
+    /// race a worker that's about to enqueue more links.
+    active_fetches: Arc<AtomicUsize>,
     config: CrawlerConfig,
     store: Arc<Mutex<Box<dyn ContentStore + Send>>>,
-    robots_cache: Arc<dashmap::DashMap<String, RobotsTxt>>,
+    robots_cache: Arc<DashMap<String, RobotsTxt>>,
+    status_filters: Arc<Vec<Box<dyn StatusFilter + Send + Sync>>>,
+    task_filters: Arc<Vec<Box<dyn TaskFilter + Send + Sync>>>,
+    link_extractor: Arc<dyn LinkExtractor + Send + Sync>,
+    document_parser: Arc<dyn DocumentParser + Send + Sync>,
+    search_index: Option<Arc<SearchIndex>>,
 }
 
 impl Crawler {
     pub async fn new(
         config: CrawlerConfig,
         store: Box<dyn ContentStore + Send>,
-    ) -> Result<Self, Box<dyn Error>> {
-        let client = Client::builder().user_agent(&config.user_agent).timeout(Duration::from_secs(10)).build()?;
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(Duration::from_secs(10))
+            .redirect(Policy::limited(config.max_redirect))
+            .build()?;
+
+        let status_filters: Vec<Box<dyn StatusFilter + Send + Sync>> = vec![Box::new(ContentTypeFilter {
+            accepted_content_types: config.accepted_content_types.clone(),
+        })];
+        let task_filters: Vec<Box<dyn TaskFilter + Send + Sync>> = vec![Box::new(DomainScopeFilter {
+            domain_allowlist: config.domain_allowlist.clone(),
+            domain_blocklist: config.domain_blocklist.clone(),
+            allow_www: config.allow_www,
+            links_per_page_budget: config.links_per_page_budget,
+        })];
+
+        let queue_permits = config.max_queue_size.map(|size| Arc::new(Semaphore::new(size)));
+
         Ok(Self {
             client,
             visited: Arc::new(DashSet::new()),
-            queue: Arc::new(Mutex::new(VecDeque::new())),
-            pages_crawled: Arc::new(Mutex::new(0)),
+            host_queues: Arc::new(DashMap::new()),
+            host_next_allowed: Arc::new(DashMap::new()),
+            host_cycle: Arc::new(Mutex::new(VecDeque::new())),
+            hosts_scheduled: Arc::new(DashSet::new()),
+            queue_permits,
+            page_budget: Arc::new(Semaphore::new(config.max_pages)),
+            pages_crawled: Arc::new(AtomicUsize::new(0)),
+            active_fetches: Arc::new(AtomicUsize::new(0)),
             config,
             store: Arc::new(Mutex::new(store)),
-            robots_cache: Arc::new(dashmap::DashMap::new()),
+            robots_cache: Arc::new(DashMap::new()),
+            status_filters: Arc::new(status_filters),
+            task_filters: Arc::new(task_filters),
+            link_extractor: Arc::new(DefaultLinkExtractor),
+            document_parser: Arc::new(DefaultDocumentParser),
+            search_index: None,
         })
     }
 
+    /// Enables full-text search: every stored page is folded into `index`,
+    /// which callers can later query with [`SearchIndex::query`].
+    pub fn with_search_index(mut self, index: Arc<SearchIndex>) -> Self {
+        self.search_index = Some(index);
+        self
+    }
+
+    /// Appends an additional `StatusFilter` to the chain consulted before a
+    /// response body is read. Must be called before [`Crawler::run`].
+    pub fn with_status_filter(mut self, filter: Box<dyn StatusFilter + Send + Sync>) -> Self {
+        Arc::get_mut(&mut self.status_filters)
+            .expect("filters can only be added before run() shares the crawler across workers")
+            .push(filter);
+        self
+    }
+
+    /// Appends an additional `TaskFilter` to the chain consulted before a
+    /// discovered link is enqueued. Must be called before [`Crawler::run`].
+    pub fn with_task_filter(mut self, filter: Box<dyn TaskFilter + Send + Sync>) -> Self {
+        Arc::get_mut(&mut self.task_filters)
+            .expect("filters can only be added before run() shares the crawler across workers")
+            .push(filter);
+        self
+    }
+
+    /// Overrides how links are extracted from a parsed document.
+    pub fn with_link_extractor(mut self, extractor: Arc<dyn LinkExtractor + Send + Sync>) -> Self {
+        self.link_extractor = extractor;
+        self
+    }
+
+    /// Overrides how title/description metadata is extracted from a parsed document.
+    pub fn with_document_parser(mut self, parser: Arc<dyn DocumentParser + Send + Sync>) -> Self {
+        self.document_parser = parser;
+        self
+    }
+
     async fn add_url(&self, url: &str, depth: usize) {
-        if let Some(normalized_url) = normalize_url(url) {
-            if !self.visited.contains(&normalized_url) {
-                self.visited.insert(normalized_url.clone());
-                self.queue.lock().await.push_back((normalized_url, depth));
+        let Some(normalized_url) = normalize_url(url) else {
+            return;
+        };
+        if self.visited.contains(&normalized_url) {
+            return;
+        }
+        let Some(host) = Url::parse(&normalized_url).ok().and_then(|u| u.host_str().map(str::to_string)) else {
+            return;
+        };
+
+        // Backpressure: every caller here is itself a worker, so blocking for
+        // a free slot would stop it from ever draining the queue it's
+        // waiting on. Drop the link instead once the frontier is full.
+        let permit = match &self.queue_permits {
+            Some(semaphore) => match semaphore.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    warn!("Frontier full, dropping discovered link {}", normalized_url);
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        self.visited.insert(normalized_url.clone());
+        // Held across both the push and the `hosts_scheduled` check so this
+        // can't interleave with `next_task` draining the same host's queue
+        // to empty and unscheduling it — see the matching lock in
+        // `next_task` for why that race used to strand hosts.
+        let mut queue = self.host_queues.entry(host.clone()).or_default();
+        queue.push_back((normalized_url, depth, permit));
+        let newly_scheduled = self.hosts_scheduled.insert(host.clone());
+        drop(queue);
+        if newly_scheduled {
+            self.host_cycle.lock().await.push_back(host);
+        }
+    }
+
+    /// Seeds the frontier from a `sitemap.xml` (or `sitemapindex.xml`),
+    /// following nested sitemaps and skipping any URL whose stored
+    /// `last_fetched` is already newer than the sitemap's `lastmod` or
+    /// `config.recrawl_after`'s TTL — letting a recrawl skip pages the site
+    /// says haven't changed instead of blindly re-fetching everything.
+    pub async fn seed_from_sitemap(&self, sitemap_url: &str) {
+        for entry in fetch_sitemap_entries(&self.client, sitemap_url).await {
+            if self.should_skip_recrawl(&entry.loc, entry.lastmod).await {
+                debug!("Skipping unchanged {} per sitemap/recrawl TTL", entry.loc);
+                continue;
+            }
+            self.add_url(&entry.loc, 0).await;
+        }
+    }
+
+    async fn should_skip_recrawl(&self, url: &str, lastmod: Option<chrono::DateTime<Utc>>) -> bool {
+        let Some(normalized) = normalize_url(url) else {
+            return false;
+        };
+        let last_fetched = match self.store.lock().await.get_last_fetched(&normalized).await {
+            Ok(Some(last_fetched)) => last_fetched,
+            _ => return false,
+        };
+        if let Some(lastmod) = lastmod {
+            if last_fetched >= lastmod {
+                return true;
+            }
+        }
+        if let Some(ttl) = self.config.recrawl_after {
+            if Utc::now() - last_fetched < ttl {
+                return true;
             }
         }
+        false
     }
 
-    pub async fn run(&self, seed_urls: Vec<String>) -> Result<CrawlResult, Box<dyn Error>> {
+    pub async fn run(&self, seed_urls: Vec<String>) -> Result<CrawlResult, Box<dyn Error + Send + Sync>> {
         let start_time = Instant::now();
-        for url in seed_urls self.add_url(&url, 0).await;
+        for url in seed_urls {
+            self.add_url(&url, 0).await;
+        }
         let mut handles = Vec::new();
         for worker_id in 0..self.config.concurrent_requests {
             let crawler_clone = self.clone();
@@ -63,8 +234,10 @@ impl Crawler {
             });
             handles.push(handle);
         }
-        for handle in handles let _ = handle.await;
-        let pages_processed = *self.pages_crawled.lock().await;
+        for handle in handles {
+            let _ = handle.await;
+        }
+        let pages_processed = self.pages_crawled.load(Ordering::SeqCst);
         let unique_urls = self.visited.len();
         let elapsed = start_time.elapsed().as_secs_f64();
 
@@ -75,55 +248,166 @@ impl Crawler {
             elapsed_seconds: elapsed,
         })
     }
+
+    /// Rotates through hosts with pending work, skipping any still inside
+    /// their crawl-delay window, and pops the next URL from the first host
+    /// that's ready. Returns `None` if no host currently has a ready URL.
+    async fn next_task(&self) -> Option<(String, String, usize)> {
+        let cycle_len = self.host_cycle.lock().await.len();
+        for _ in 0..cycle_len {
+            let host = self.host_cycle.lock().await.pop_front()?;
+
+            let ready = self
+                .host_next_allowed
+                .get(&host)
+                .map(|next| *next <= Instant::now())
+                .unwrap_or(true);
+            if !ready {
+                self.host_cycle.lock().await.push_back(host);
+                continue;
+            }
+
+            // Pop (and, if that empties the queue, unschedule) under the
+            // same per-host lock `add_url` holds for its push+insert, so
+            // the two can't interleave: without this, a worker could see
+            // `pop_front() == None` here while `add_url` concurrently
+            // pushed a fresh URL and found the host already in
+            // `hosts_scheduled` (so it skipped re-adding to `host_cycle`),
+            // leaving that URL stranded in neither structure.
+            let mut queue = self.host_queues.entry(host.clone()).or_default();
+            let popped = queue.pop_front();
+            match popped {
+                // Dropping `_permit` here frees its frontier slot now that
+                // the URL is leaving the queue for active processing.
+                Some((url, depth, _permit)) => {
+                    drop(queue);
+                    // Reserve this host's delay window at selection time,
+                    // not after the fetch completes: arming it only in
+                    // `process_task` let every worker that raced in before
+                    // the first one finished pop the same host and fetch it
+                    // concurrently, which is exactly the "N workers hammer
+                    // one domain" behavior crawl-delay is meant to prevent.
+                    let delay_ms = self
+                        .robots_cache
+                        .get(&host)
+                        .and_then(|robots| robots.crawl_delay())
+                        .map(|delay| delay.as_millis() as u64)
+                        .unwrap_or(0)
+                        .max(self.config.delay_ms);
+                    self.host_next_allowed
+                        .insert(host.clone(), Instant::now() + Duration::from_millis(delay_ms));
+                    self.host_cycle.lock().await.push_back(host.clone());
+                    return Some((host, url, depth));
+                }
+                None => {
+                    self.hosts_scheduled.remove(&host);
+                }
+            }
+        }
+        None
+    }
+
+    fn frontier_is_empty(&self) -> bool {
+        self.host_cycle
+            .try_lock()
+            .map(|cycle| cycle.is_empty())
+            .unwrap_or(false)
+    }
+
     async fn worker(&self, worker_id: usize) {
-        debug!("Worker {} starting", worker_id);   
+        debug!("Worker {} starting", worker_id);
         loop {
-            {
-                let pages_crawled = *self.pages_crawled.lock().await;
-                if pages_crawled >= self.config.max_pages {
+            // Acquired before the task is even looked up so "is there budget
+            // left" and "spend it" are one atomic step; checking
+            // `available_permits()` and forgetting a permit afterwards (as
+            // this used to do) lets two workers both pass the check before
+            // either spends its permit, overshooting `max_pages`.
+            let permit = match self.page_budget.clone().try_acquire_owned() {
+                Ok(permit) => permit,
+                Err(_) => {
                     debug!("Worker {} stopping: max pages reached", worker_id);
                     break;
                 }
-            }
-            let next_item = {
-                let mut queue = self.queue.lock().await;
-                queue.pop_front()
             };
-            if let Some((url, depth)) = next_item {
-                if depth <= self.config.max_depth {
-                    if self.config.respect_robots_txt {
-                        let can_crawl = self.check_robots_txt(&url).await;
-                        if !can_crawl {
-                            debug!("Skipping {} due to robots.txt", url);
-                            continue;
-                        }
-                    }
-                    match self.crawl_page(&url, depth).await {
-                        Ok(page) => {
-                            if let Err(e) = self.store.lock().await.add_page(page.clone()) error!("Failed to store page {}: {}", url, e);
-                            for link in &page.links {
-                                self.add_url(link, depth + 1).await;
-                            }
-                            info!("Worker {}: Crawled {} (depth: {})", worker_id, url, depth);
-                            {
-                                let mut pages_crawled = self.pages_crawled.lock().await;
-                                *pages_crawled += 1;
-                            }
-                        }
-                        Err(e) => error!("Worker {}: Error crawling {}: {}", worker_id, url, e),
+            match self.next_task().await {
+                Some((host, url, depth)) => {
+                    self.active_fetches.fetch_add(1, Ordering::SeqCst);
+                    let crawled = self.process_task(worker_id, &host, &url, depth).await;
+                    self.active_fetches.fetch_sub(1, Ordering::SeqCst);
+                    if crawled {
+                        permit.forget();
+                        self.pages_crawled.fetch_add(1, Ordering::SeqCst);
                     }
-                    sleep(Duration::from_millis(self.config.delay_ms)).await;
                 }
-            } else {
-                sleep(Duration::from_millis(100)).await;
-                if self.queue.lock().await.is_empty() {
-                    debug!("Worker {} stopping: queue empty", worker_id);
-                    break;
+                None => {
+                    drop(permit);
+                    sleep(Duration::from_millis(100)).await;
+                    if self.frontier_is_empty() && self.active_fetches.load(Ordering::SeqCst) == 0 {
+                        debug!("Worker {} stopping: frontier empty", worker_id);
+                        break;
+                    }
                 }
             }
         }
     }
 
+    /// Fetches and stores a single task, enqueuing its links and updating
+    /// the host's crawl-delay. Runs entirely inside `active_fetches`'s
+    /// bracket so shutdown can't observe an empty frontier mid-page. Returns
+    /// whether a page was actually crawled, so the caller knows whether to
+    /// spend its page-budget permit.
+    async fn process_task(&self, worker_id: usize, host: &str, url: &str, depth: usize) -> bool {
+        if depth > self.config.max_depth {
+            return false;
+        }
+        if self.config.respect_robots_txt && !self.check_robots_txt(url).await {
+            debug!("Skipping {} due to robots.txt", url);
+            return false;
+        }
+        let crawled = match self.crawl_page(url, depth).await {
+            Ok(page) => {
+                let links = page.links.clone();
+                if let Some(index) = &self.search_index {
+                    index.index_page(&page);
+                }
+                if let Err(e) = self.store.lock().await.add_page(page).await {
+                    error!("Failed to store page {}: {}", url, e);
+                }
+                let mut links_from_page = 0;
+                for link in &links {
+                    if let Ok(parsed_link) = Url::parse(link) {
+                        let accepted = self
+                            .task_filters
+                            .iter()
+                            .all(|filter| filter.accept(&parsed_link, depth, links_from_page));
+                        if accepted {
+                            self.add_url(link, depth + 1).await;
+                            links_from_page += 1;
+                        }
+                    }
+                }
+                info!("Worker {}: Crawled {} (depth: {})", worker_id, url, depth);
+                true
+            }
+            Err(e) => {
+                error!("Worker {}: Error crawling {}: {}", worker_id, url, e);
+                false
+            }
+        };
+
+        let delay_ms = self
+            .robots_cache
+            .get(host)
+            .and_then(|robots| robots.crawl_delay())
+            .map(|delay| delay.as_millis() as u64)
+            .unwrap_or(0)
+            .max(self.config.delay_ms);
+        self.host_next_allowed
+            .insert(host.to_string(), Instant::now() + Duration::from_millis(delay_ms));
+
+        crawled
+    }
+
     async fn check_robots_txt(&self, url: &str) -> bool {
         let parsed_url = match Url::parse(url) {
             Ok(url) => url,
@@ -141,7 +425,7 @@ impl Crawler {
             Ok(url) => url,
             Err(_) => return true,
         };
-        match RobotsTxt::fetch(&self.client, &robots_url).await {
+        match RobotsTxt::fetch(&self.client, &robots_url, &self.config.user_agent).await {
             Some(robots) => {
                 let path = parsed_url.path();
                 let allowed = robots.is_allowed(path);
@@ -151,55 +435,55 @@ impl Crawler {
             None => true,
         }
     }
-    async fn crawl_page(&self, url: &str, depth: usize) -> Result<Page, Box<dyn Error>> {
+
+    async fn crawl_page(&self, url: &str, depth: usize) -> Result<Page, Box<dyn Error + Send + Sync>> {
         let response = self.client.get(url).send().await?;
-        if !response.status().is_success() return Err(format!("Failed to fetch page: {}", response.status()).into());
-        let content_type = response.headers().get("content-type").and_then(|value| value.to_str().ok()).unwrap_or("");
-        if !content_type.contains("text/html") return Err("Not an HTML page".into());
+        let status = response.status();
+        if !self
+            .status_filters
+            .iter()
+            .all(|filter| filter.should_process(status, response.headers()))
+        {
+            return Err(format!("Rejected by status filter: {}", status).into());
+        }
         let html_content = response.text().await?;
         let document = Html::parse_document(&html_content);
-        let title_selector = Selector::parse("title").unwrap();
-        let title = document.select(&title_selector).next().map(|element| element.text().collect::<String>());
-        let meta_selector = Selector::parse("meta[name='description']").unwrap();
-        let description = document.select(&meta_selector).next().and_then(|element| element.value().attr("content")).map(String::from);
-        let link_selector = Selector::parse("a[href]").unwrap();
+        let (title, description) = self.document_parser.parse(&document);
         let base_url = Url::parse(url)?;
-        let links = document.select(&link_selector).filter_map(|element| {
-                element.value().attr("href").and_then(|href| {
-                    match base_url.join(href) {
-                        Ok(absolute_url) => {
-                            // Only keep http and https schemes
-                            if absolute_url.scheme() == "http" || absolute_url.scheme() == "https" {
-                                Some(absolute_url.to_string())
-                            } else {
-                                None
-                            }
-                        }
-                        Err(_) => None,
-                    }
-                })
-            })
-            .collect();
+        let links = self.link_extractor.extract_links(&document, &base_url);
 
         Ok(Page {
             url: url.to_string(),
+            status: status.as_u16(),
             title,
             description,
             content: html_content,
             links,
             crawl_depth: depth,
-            crawl_time: chrono::Utc::now(),
+            last_fetched: chrono::Utc::now(),
         })
     }
+
     fn clone(&self) -> Self {
         Self {
             client: self.client.clone(),
             visited: Arc::clone(&self.visited),
-            queue: Arc::clone(&self.queue),
+            host_queues: Arc::clone(&self.host_queues),
+            host_next_allowed: Arc::clone(&self.host_next_allowed),
+            host_cycle: Arc::clone(&self.host_cycle),
+            hosts_scheduled: Arc::clone(&self.hosts_scheduled),
+            queue_permits: self.queue_permits.clone(),
+            page_budget: Arc::clone(&self.page_budget),
             pages_crawled: Arc::clone(&self.pages_crawled),
+            active_fetches: Arc::clone(&self.active_fetches),
             store: Arc::clone(&self.store),
             robots_cache: Arc::clone(&self.robots_cache),
+            status_filters: Arc::clone(&self.status_filters),
+            task_filters: Arc::clone(&self.task_filters),
+            link_extractor: Arc::clone(&self.link_extractor),
+            document_parser: Arc::clone(&self.document_parser),
+            search_index: self.search_index.clone(),
             config: self.config.clone(),
         }
     }
-}
\ No newline at end of file
+}
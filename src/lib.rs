@@ -0,0 +1,8 @@
+pub mod config;
+pub mod crawler;
+pub mod robots;
+pub mod rules;
+pub mod search;
+pub mod sitemap;
+pub mod storage;
+pub mod utils;
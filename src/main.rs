@@ -1,45 +1,85 @@
+use cap::Cap;
 use clap::Parser;
 use log::{info, LevelFilter};
+use std::alloc::System;
 
 use multithreaded_web_crawler::config::CrawlerConfig;
 use multithreaded_web_crawler::crawler::Crawler;
-use multithreaded_web_crawler::storage::memory::MemoryStore;
+use multithreaded_web_crawler::storage::{ContentStore, MemoryStore, SqliteStore};
+
+/// Global allocator wrapped so `--max-memory-mb` can cap the crawler's
+/// resident memory instead of letting an unbounded crawl OOM the host.
+#[global_allocator]
+static ALLOCATOR: Cap<System> = Cap::new(System, usize::MAX);
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-
 struct Args {
     #[arg(required = true)]
     seed_urls: Vec<String>,
-    #[args(short, long, default_value_t = 2)]
+    #[arg(short, long, default_value_t = 2)]
     depth: usize,
-    #[args(short, long, default_value_t = 8)]
-    depth: usize,
-    #[args(short, long, default_value_t = 200)]
-    depth: u64,
-    #[args(long, default_value_t = true)] 
+    #[arg(short, long, default_value_t = 100)]
+    max_pages: usize,
+    #[arg(short, long, default_value_t = 8)]
+    concurrent: usize,
+    #[arg(long, default_value_t = 200)]
+    delay: u64,
+    #[arg(long, default_value_t = true)]
     respect_robots: bool,
+    /// sqlite database URL (e.g. sqlite://crawl.db); falls back to an
+    /// in-memory store when omitted
+    #[arg(long)]
+    database_url: Option<String>,
+    /// directory used for the on-disk page cache when `--database-url` is set
+    #[arg(long, default_value = "crawl-cache")]
+    cache_dir: String,
+    /// maximum number of URLs allowed to sit in the frontier at once; unset
+    /// means unbounded
+    #[arg(long)]
+    max_queue_size: Option<usize>,
+    /// caps the process's resident memory in megabytes; 0 means unbounded
+    #[arg(long, default_value_t = 0)]
+    max_memory_mb: usize,
+    /// sitemap.xml (or sitemapindex.xml) URL to seed the frontier from, in
+    /// addition to `seed_urls`; may be passed more than once
+    #[arg(long = "sitemap")]
+    sitemaps: Vec<String>,
 }
 
-#[tokio::main] 
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     env_logger::builder().filter_level(LevelFilter::Info).init();
     let args = Args::parse();
+
+    if args.max_memory_mb > 0 {
+        ALLOCATOR
+            .set_limit(args.max_memory_mb * 1024 * 1024)
+            .expect("invalid --max-memory-mb");
+    }
+
     let config = CrawlerConfig {
         max_depth: args.depth,
         max_pages: args.max_pages,
         concurrent_requests: args.concurrent,
         delay_ms: args.delay,
-        user_agent: "RustWebCrawler/1.0 (https://example.com/bot)".to_string(),
         respect_robots_txt: args.respect_robots,
+        max_queue_size: args.max_queue_size,
+        ..CrawlerConfig::default()
     };
 
-    let store = MemoryStore::new();
-    let crawler = Crawler::new(config, Box::new(store)).await?;
-    let result = crawler.crawl(args.seed_urls).await?;
+    let store: Box<dyn ContentStore + Send> = match &args.database_url {
+        Some(database_url) => Box::new(SqliteStore::new(database_url, &args.cache_dir).await?),
+        None => Box::new(MemoryStore::new()),
+    };
+    let crawler = Crawler::new(config, store).await?;
+    for sitemap_url in &args.sitemaps {
+        crawler.seed_from_sitemap(sitemap_url).await;
+    }
+    let result = crawler.run(args.seed_urls).await?;
 
-    info!("Crawling completed. Found {} pages.", result.paper_processed);
+    info!("Crawling completed. Found {} pages.", result.pages_processed);
     info!("Crawled {} unique URLs.", result.unique_urls);
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,276 @@
+use reqwest::Client;
+use std::time::Duration;
+use url::Url;
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: String,
+    allow: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Group {
+    user_agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<f64>,
+}
+
+/// An RFC 9309 robots.txt: parsed into per-user-agent rule groups so
+/// `is_allowed` can apply longest-match-wins precedence instead of
+/// returning on the first matching prefix.
+#[derive(Debug, Clone)]
+pub struct RobotsTxt {
+    groups: Vec<Group>,
+    sitemaps: Vec<String>,
+    user_agent: String,
+}
+
+impl RobotsTxt {
+    pub async fn fetch(client: &Client, robots_url: &Url, user_agent: &str) -> Option<Self> {
+        let response = client.get(robots_url.as_str()).send().await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        let content = response.text().await.ok()?;
+        Some(Self::parse(&content, user_agent))
+    }
+
+    fn parse(content: &str, user_agent: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut sitemaps = Vec::new();
+        let mut current: Option<Group> = None;
+        let mut seen_rule_in_group = false;
+
+        for raw_line in content.lines() {
+            let line = strip_comment(raw_line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let field = field.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            match field.as_str() {
+                "user-agent" => {
+                    if seen_rule_in_group || current.is_none() {
+                        if let Some(group) = current.take() {
+                            groups.push(group);
+                        }
+                        current = Some(Group::default());
+                        seen_rule_in_group = false;
+                    }
+                    current.get_or_insert_with(Group::default)
+                        .user_agents
+                        .push(value.to_ascii_lowercase());
+                }
+                "allow" | "disallow" => {
+                    // An empty value (e.g. a bare `Disallow:`) means "no
+                    // restriction"; a pattern-"" rule would match every path.
+                    if !value.is_empty() {
+                        let group = current.get_or_insert_with(Group::default);
+                        group.rules.push(Rule {
+                            pattern: value.to_string(),
+                            allow: field == "allow",
+                        });
+                    }
+                    seen_rule_in_group = true;
+                }
+                "crawl-delay" => {
+                    if let Ok(seconds) = value.parse::<f64>() {
+                        current.get_or_insert_with(Group::default).crawl_delay = Some(seconds);
+                    }
+                    seen_rule_in_group = true;
+                }
+                "sitemap" => sitemaps.push(value.to_string()),
+                _ => {}
+            }
+        }
+        if let Some(group) = current.take() {
+            groups.push(group);
+        }
+
+        Self {
+            groups,
+            sitemaps,
+            user_agent: user_agent.to_ascii_lowercase(),
+        }
+    }
+
+    /// The group whose `User-agent` best matches our configured agent: the
+    /// longest `User-agent` value contained (case-insensitively) in our
+    /// agent string wins, with the `*` group as a fallback.
+    fn matching_group(&self) -> Option<&Group> {
+        let mut best: Option<(&Group, usize)> = None;
+        for group in &self.groups {
+            for agent in &group.user_agents {
+                if agent != "*"
+                    && self.user_agent.contains(agent.as_str())
+                    && best.map_or(true, |(_, best_len)| agent.len() > best_len)
+                {
+                    best = Some((group, agent.len()));
+                }
+            }
+        }
+        best.map(|(group, _)| group)
+            .or_else(|| {
+                self.groups
+                    .iter()
+                    .find(|group| group.user_agents.iter().any(|agent| agent == "*"))
+            })
+    }
+
+    pub fn is_allowed(&self, path: &str) -> bool {
+        let Some(group) = self.matching_group() else {
+            return true;
+        };
+
+        // Longest matching pattern wins; Allow wins ties against Disallow.
+        let mut best: Option<(&Rule, usize)> = None;
+        for rule in &group.rules {
+            if !pattern_matches(&rule.pattern, path) {
+                continue;
+            }
+            let len = rule.pattern.len();
+            let replace = match best {
+                None => true,
+                Some((best_rule, best_len)) => {
+                    len > best_len || (len == best_len && rule.allow && !best_rule.allow)
+                }
+            };
+            if replace {
+                best = Some((rule, len));
+            }
+        }
+
+        best.map(|(rule, _)| rule.allow).unwrap_or(true)
+    }
+
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        let seconds = self.matching_group()?.crawl_delay?;
+        Some(Duration::from_secs_f64(seconds.max(0.0)))
+    }
+
+    pub fn sitemaps(&self) -> &[String] {
+        &self.sitemaps
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(idx) => &line[..idx],
+        None => line,
+    }
+}
+
+/// Matches a robots.txt path pattern against `path`, supporting `*` (any
+/// sequence of characters) and a trailing `$` (anchors the match to the
+/// end of the path).
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    if pattern.is_empty() {
+        return true;
+    }
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(stripped) => (stripped, true),
+        None => (pattern, false),
+    };
+    let segments: Vec<&str> = pattern.split('*').collect();
+
+    let mut rest = path;
+    for (i, segment) in segments.iter().enumerate() {
+        if segment.is_empty() {
+            continue;
+        }
+        if i == 0 {
+            if !rest.starts_with(segment) {
+                return false;
+            }
+            rest = &rest[segment.len()..];
+        } else if anchored && i == segments.len() - 1 {
+            // An anchored final segment must end the path, not merely
+            // appear somewhere in it.
+            return rest.ends_with(segment);
+        } else {
+            match rest.find(segment) {
+                Some(idx) => rest = &rest[idx + segment.len()..],
+                None => return false,
+            }
+        }
+    }
+
+    if anchored {
+        let last_is_empty = segments.last().map(|s| s.is_empty()).unwrap_or(true);
+        if last_is_empty {
+            true
+        } else {
+            rest.is_empty()
+        }
+    } else {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn robots(content: &str, user_agent: &str) -> RobotsTxt {
+        RobotsTxt::parse(content, user_agent)
+    }
+
+    #[test]
+    fn falls_back_to_wildcard_group() {
+        let robots = robots(
+            "User-agent: *\nDisallow: /private\n",
+            "RustWebCrawler/1.0",
+        );
+        assert!(!robots.is_allowed("/private/page"));
+        assert!(robots.is_allowed("/public"));
+    }
+
+    #[test]
+    fn prefers_specific_user_agent_group() {
+        let robots = robots(
+            "User-agent: *\nDisallow: /\n\nUser-agent: RustWebCrawler\nDisallow:\n",
+            "RustWebCrawler/1.0",
+        );
+        assert!(robots.is_allowed("/anything"));
+    }
+
+    #[test]
+    fn longest_match_wins_over_allow_disallow_order() {
+        let robots = robots(
+            "User-agent: *\nDisallow: /a\nAllow: /a/b\n",
+            "RustWebCrawler/1.0",
+        );
+        assert!(robots.is_allowed("/a/b/c"));
+        assert!(!robots.is_allowed("/a/x"));
+    }
+
+    #[test]
+    fn allow_wins_ties() {
+        let robots = robots("User-agent: *\nDisallow: /a\nAllow: /a\n", "RustWebCrawler/1.0");
+        assert!(robots.is_allowed("/a"));
+    }
+
+    #[test]
+    fn wildcard_and_end_anchor_patterns() {
+        let robots = robots(
+            "User-agent: *\nDisallow: /*.pdf$\n",
+            "RustWebCrawler/1.0",
+        );
+        assert!(!robots.is_allowed("/files/report.pdf"));
+        assert!(robots.is_allowed("/files/report.pdf.html"));
+    }
+
+    #[test]
+    fn parses_crawl_delay_and_sitemap() {
+        let robots = robots(
+            "User-agent: *\nCrawl-delay: 2.5\nSitemap: https://example.com/sitemap.xml\n",
+            "RustWebCrawler/1.0",
+        );
+        assert_eq!(robots.crawl_delay(), Some(Duration::from_secs_f64(2.5)));
+        assert_eq!(robots.sitemaps(), &["https://example.com/sitemap.xml".to_string()]);
+    }
+}
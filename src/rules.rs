@@ -0,0 +1,140 @@
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use scraper::{Html, Selector};
+use url::Url;
+
+/// Decides whether to keep processing a response based on its status line
+/// and headers, before the (potentially large) body is read.
+pub trait StatusFilter {
+    fn should_process(&self, status: StatusCode, headers: &HeaderMap) -> bool;
+}
+
+/// Decides whether a link discovered on a page should be enqueued.
+pub trait TaskFilter {
+    fn accept(&self, url: &Url, source_depth: usize, links_from_page: usize) -> bool;
+}
+
+/// Pulls outbound links out of a parsed document.
+pub trait LinkExtractor {
+    fn extract_links(&self, document: &Html, base_url: &Url) -> Vec<String>;
+}
+
+/// Pulls page metadata (title, description) out of a parsed document.
+pub trait DocumentParser {
+    fn parse(&self, document: &Html) -> (Option<String>, Option<String>);
+}
+
+/// Accepts responses whose status is successful and whose `Content-Type`
+/// matches one of `accepted_content_types`.
+pub struct ContentTypeFilter {
+    pub accepted_content_types: Vec<String>,
+}
+
+impl StatusFilter for ContentTypeFilter {
+    fn should_process(&self, status: StatusCode, headers: &HeaderMap) -> bool {
+        if !status.is_success() {
+            return false;
+        }
+        let content_type = headers
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("");
+        self.accepted_content_types
+            .iter()
+            .any(|accepted| content_type.contains(accepted.as_str()))
+    }
+}
+
+/// Enforces domain scope (allow/block lists, `www.` normalization) and a
+/// per-page link budget so a single page can't flood the frontier.
+pub struct DomainScopeFilter {
+    pub domain_allowlist: Vec<String>,
+    pub domain_blocklist: Vec<String>,
+    pub allow_www: bool,
+    pub links_per_page_budget: Option<usize>,
+}
+
+impl DomainScopeFilter {
+    fn normalized_host(&self, url: &Url) -> Option<String> {
+        let host = url.host_str()?;
+        if self.allow_www {
+            Some(host.strip_prefix("www.").unwrap_or(host).to_string())
+        } else {
+            Some(host.to_string())
+        }
+    }
+}
+
+impl TaskFilter for DomainScopeFilter {
+    fn accept(&self, url: &Url, _source_depth: usize, links_from_page: usize) -> bool {
+        if let Some(budget) = self.links_per_page_budget {
+            if links_from_page >= budget {
+                return false;
+            }
+        }
+        let Some(host) = self.normalized_host(url) else {
+            return false;
+        };
+        let same_host = |listed: &String| {
+            let listed = if self.allow_www {
+                listed.strip_prefix("www.").unwrap_or(listed)
+            } else {
+                listed.as_str()
+            };
+            listed == host
+        };
+        if self.domain_blocklist.iter().any(same_host) {
+            return false;
+        }
+        if self.domain_allowlist.is_empty() {
+            return true;
+        }
+        self.domain_allowlist.iter().any(same_host)
+    }
+}
+
+/// The crawler's original extraction logic: the first `<title>` and
+/// `meta[name=description]` element in the document.
+#[derive(Default)]
+pub struct DefaultDocumentParser;
+
+impl DocumentParser for DefaultDocumentParser {
+    fn parse(&self, document: &Html) -> (Option<String>, Option<String>) {
+        let title_selector = Selector::parse("title").unwrap();
+        let title = document
+            .select(&title_selector)
+            .next()
+            .map(|element| element.text().collect::<String>());
+        let meta_selector = Selector::parse("meta[name='description']").unwrap();
+        let description = document
+            .select(&meta_selector)
+            .next()
+            .and_then(|element| element.value().attr("content"))
+            .map(String::from);
+        (title, description)
+    }
+}
+
+/// The crawler's original link extraction logic: every `a[href]` resolved
+/// against the page's base URL, keeping only `http`/`https` targets.
+#[derive(Default)]
+pub struct DefaultLinkExtractor;
+
+impl LinkExtractor for DefaultLinkExtractor {
+    fn extract_links(&self, document: &Html, base_url: &Url) -> Vec<String> {
+        let link_selector = Selector::parse("a[href]").unwrap();
+        document
+            .select(&link_selector)
+            .filter_map(|element| {
+                element.value().attr("href").and_then(|href| {
+                    let absolute_url = base_url.join(href).ok()?;
+                    if absolute_url.scheme() == "http" || absolute_url.scheme() == "https" {
+                        Some(absolute_url.to_string())
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect()
+    }
+}
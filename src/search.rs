@@ -0,0 +1,166 @@
+use crate::storage::Page;
+
+use dashmap::DashMap;
+use scraper::Html;
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "he", "in", "is",
+    "it", "its", "of", "on", "that", "the", "to", "was", "were", "will", "with",
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Posting {
+    doc_id: usize,
+    term_freq: usize,
+}
+
+/// In-process inverted index over crawled pages, ranked with BM25. Built up
+/// incrementally as pages are stored so crawled output is queryable without
+/// a separate indexing pass.
+#[derive(Default)]
+pub struct SearchIndex {
+    postings: DashMap<String, Vec<Posting>>,
+    doc_lengths: DashMap<usize, usize>,
+    urls: DashMap<usize, String>,
+    next_doc_id: AtomicUsize,
+    total_doc_length: AtomicUsize,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tokenizes the page's title, description, and extracted body text,
+    /// and folds the result into the postings list and document-length
+    /// statistics that `query` scores against.
+    pub fn index_page(&self, page: &Page) {
+        let doc_id = self.next_doc_id.fetch_add(1, Ordering::SeqCst);
+        self.urls.insert(doc_id, page.url.clone());
+
+        let mut text = String::new();
+        if let Some(title) = &page.title {
+            text.push_str(title);
+            text.push(' ');
+        }
+        if let Some(description) = &page.description {
+            text.push_str(description);
+            text.push(' ');
+        }
+        text.push_str(&extract_body_text(&page.content));
+
+        let tokens = tokenize(&text);
+        self.doc_lengths.insert(doc_id, tokens.len());
+        self.total_doc_length.fetch_add(tokens.len(), Ordering::SeqCst);
+
+        let mut term_freqs: HashMap<String, usize> = HashMap::new();
+        for token in tokens {
+            *term_freqs.entry(token).or_insert(0) += 1;
+        }
+        for (term, term_freq) in term_freqs {
+            self.postings
+                .entry(term)
+                .or_default()
+                .push(Posting { doc_id, term_freq });
+        }
+    }
+
+    /// Scores every indexed document against `query` with BM25 and returns
+    /// the top `k` URLs by descending score.
+    pub fn query(&self, query: &str, k: usize) -> Vec<(String, f64)> {
+        let doc_count = self.doc_lengths.len();
+        if doc_count == 0 {
+            return Vec::new();
+        }
+        let avg_doc_len = self.total_doc_length.load(Ordering::SeqCst) as f64 / doc_count as f64;
+
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for term in tokenize(query) {
+            let Some(postings) = self.postings.get(&term) else {
+                continue;
+            };
+            let df = postings.len();
+            let idf = ((doc_count as f64 - df as f64 + 0.5) / (df as f64 + 0.5) + 1.0).ln();
+            for posting in postings.iter() {
+                let doc_len = self.doc_lengths.get(&posting.doc_id).map(|len| *len).unwrap_or(0) as f64;
+                let tf = posting.term_freq as f64;
+                let denom = tf + K1 * (1.0 - B + B * doc_len / avg_doc_len);
+                *scores.entry(posting.doc_id).or_insert(0.0) += idf * (tf * (K1 + 1.0)) / denom;
+            }
+        }
+
+        let mut ranked: Vec<(usize, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(CmpOrdering::Equal));
+        ranked.truncate(k);
+
+        ranked
+            .into_iter()
+            .filter_map(|(doc_id, score)| self.urls.get(&doc_id).map(|url| (url.clone(), score)))
+            .collect()
+    }
+}
+
+fn extract_body_text(html: &str) -> String {
+    Html::parse_document(html)
+        .root_element()
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty() && !STOPWORDS.contains(token))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn page(url: &str, title: &str, content: &str) -> Page {
+        Page {
+            url: url.to_string(),
+            status: 200,
+            title: Some(title.to_string()),
+            description: None,
+            content: content.to_string(),
+            links: Vec::new(),
+            crawl_depth: 0,
+            last_fetched: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn ranks_the_more_relevant_document_first() {
+        let index = SearchIndex::new();
+        index.index_page(&page(
+            "https://a.example/",
+            "Rust programming",
+            "<body>Rust is a systems programming language. Rust Rust Rust.</body>",
+        ));
+        index.index_page(&page(
+            "https://b.example/",
+            "Gardening tips",
+            "<body>Tips for growing tomatoes in your garden.</body>",
+        ));
+
+        let results = index.query("rust programming", 5);
+        assert_eq!(results.first().map(|(url, _)| url.as_str()), Some("https://a.example/"));
+    }
+
+    #[test]
+    fn empty_index_returns_no_results() {
+        let index = SearchIndex::new();
+        assert!(index.query("anything", 5).is_empty());
+    }
+}
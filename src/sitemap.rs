@@ -0,0 +1,111 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use reqwest::Client;
+use std::collections::HashSet;
+
+/// One `<url>` entry parsed out of a sitemap, with its publisher-supplied
+/// `lastmod` (if any) for incremental-recrawl comparisons.
+#[derive(Debug, Clone)]
+pub struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// Fetches `sitemap_url` and returns every page entry it describes,
+/// following `<sitemapindex>` entries to their nested sitemaps. Unreachable
+/// or malformed sitemaps are skipped rather than failing the whole fetch.
+pub async fn fetch_sitemap_entries(client: &Client, sitemap_url: &str) -> Vec<SitemapEntry> {
+    let mut entries = Vec::new();
+    let mut pending = vec![sitemap_url.to_string()];
+    let mut seen = HashSet::new();
+
+    while let Some(url) = pending.pop() {
+        if !seen.insert(url.clone()) {
+            continue;
+        }
+        let Ok(response) = client.get(&url).send().await else {
+            continue;
+        };
+        let Ok(body) = response.text().await else {
+            continue;
+        };
+        let (page_entries, child_sitemaps) = parse_sitemap_xml(&body);
+        entries.extend(page_entries);
+        pending.extend(child_sitemaps);
+    }
+
+    entries
+}
+
+/// Splits a sitemap document into page entries (`<url>`) and nested sitemap
+/// locations (`<sitemap>`), handling both `<urlset>` and `<sitemapindex>`.
+fn parse_sitemap_xml(body: &str) -> (Vec<SitemapEntry>, Vec<String>) {
+    let mut reader = Reader::from_str(body);
+    reader.trim_text(true);
+    let mut buf = Vec::new();
+
+    let mut entries = Vec::new();
+    let mut child_sitemaps = Vec::new();
+
+    let mut in_sitemap_entry = false;
+    let mut current_tag: Option<String> = None;
+    let mut current_loc: Option<String> = None;
+    let mut current_lastmod: Option<String> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(start)) => {
+                let name = String::from_utf8_lossy(start.name().as_ref()).to_string();
+                match name.as_str() {
+                    "sitemap" => in_sitemap_entry = true,
+                    "url" => in_sitemap_entry = false,
+                    _ => {}
+                }
+                current_tag = Some(name);
+            }
+            Ok(Event::Text(text)) => {
+                if let Ok(unescaped) = text.unescape() {
+                    match current_tag.as_deref() {
+                        Some("loc") => current_loc = Some(unescaped.to_string()),
+                        Some("lastmod") => current_lastmod = Some(unescaped.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+            Ok(Event::End(end)) => {
+                let name = String::from_utf8_lossy(end.name().as_ref()).to_string();
+                if name == "sitemap" || name == "url" {
+                    if let Some(loc) = current_loc.take() {
+                        let lastmod = current_lastmod.take().and_then(|value| parse_lastmod(&value));
+                        if in_sitemap_entry {
+                            child_sitemaps.push(loc);
+                        } else {
+                            entries.push(SitemapEntry { loc, lastmod });
+                        }
+                    }
+                }
+                current_tag = None;
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    (entries, child_sitemaps)
+}
+
+/// Parses a `<lastmod>` value, which per the sitemaps spec may be a full
+/// RFC 3339 timestamp or a bare W3C date (`2024-01-01`); the latter is
+/// treated as midnight UTC.
+fn parse_lastmod(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|naive| naive.and_utc())
+}
@@ -0,0 +1,40 @@
+use super::{ContentStore, Page};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::error::Error;
+
+/// Keeps every crawled `Page`, body included, in a `Vec`. Simple, but the
+/// whole crawl has to fit in RAM and nothing survives process exit.
+#[derive(Default)]
+pub struct MemoryStore {
+    pages: Vec<Page>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ContentStore for MemoryStore {
+    async fn add_page(&mut self, page: Page) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.pages.push(page);
+        Ok(())
+    }
+
+    async fn get_all_pages(&self) -> Result<Vec<Page>, Box<dyn Error + Send + Sync>> {
+        Ok(self.pages.clone())
+    }
+
+    async fn get_last_fetched(
+        &self,
+        url: &str,
+    ) -> Result<Option<DateTime<Utc>>, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .pages
+            .iter()
+            .find(|page| page.url == url)
+            .map(|page| page.last_fetched))
+    }
+}
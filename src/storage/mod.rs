@@ -0,0 +1,39 @@
+pub mod memory;
+pub mod sqlite;
+
+pub use memory::MemoryStore;
+pub use sqlite::SqliteStore;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// A single crawled page and the metadata gathered while fetching it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page {
+    pub url: String,
+    pub status: u16,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub content: String,
+    pub links: Vec<String>,
+    pub crawl_depth: usize,
+    pub last_fetched: DateTime<Utc>,
+}
+
+/// Persists crawled pages. Implementations decide how (and whether) the
+/// raw page body is retained; callers should not assume `get_all_pages`
+/// is cheap.
+#[async_trait]
+pub trait ContentStore {
+    async fn add_page(&mut self, page: Page) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn get_all_pages(&self) -> Result<Vec<Page>, Box<dyn Error + Send + Sync>>;
+    /// The `last_fetched` timestamp recorded for `url`, if it has been
+    /// crawled before. Used to skip re-fetching pages a sitemap or recrawl
+    /// TTL says haven't changed.
+    async fn get_last_fetched(
+        &self,
+        url: &str,
+    ) -> Result<Option<DateTime<Utc>>, Box<dyn Error + Send + Sync>>;
+}
@@ -0,0 +1,122 @@
+use super::{ContentStore, Page};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Row, SqlitePool};
+use std::error::Error;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Durable, memory-bounded `ContentStore`: page metadata lives in a SQLite
+/// `pages` table (one row per URL, upserted on re-crawl) while the whole
+/// `Page`, raw HTML body included, is bincode-serialized and written to a
+/// file under `cache_dir` named by the SHA-256 hash of the page's URL. This
+/// keeps the database small and keeps bodies out of RAM between writes;
+/// `get_all_pages` reads every cached page back off disk and returns them
+/// all at once in a single `Vec`.
+pub struct SqliteStore {
+    pool: SqlitePool,
+    cache_dir: PathBuf,
+}
+
+impl SqliteStore {
+    pub async fn new(
+        database_url: &str,
+        cache_dir: impl Into<PathBuf>,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        // `connect` alone leaves sqlx's default `create_if_missing(false)` in
+        // place, so a fresh database file fails to open on the first crawl.
+        let options = SqliteConnectOptions::from_str(database_url)?.create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS pages (
+                url TEXT PRIMARY KEY,
+                status INTEGER NOT NULL,
+                title TEXT,
+                description TEXT,
+                last_fetched TEXT NOT NULL,
+                crawl_depth INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        let cache_dir = cache_dir.into();
+        std::fs::create_dir_all(&cache_dir)?;
+
+        Ok(Self { pool, cache_dir })
+    }
+
+    fn cache_path(&self, url: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(url.as_bytes());
+        let hash = format!("{:x}", hasher.finalize());
+        self.cache_dir.join(hash)
+    }
+}
+
+#[async_trait]
+impl ContentStore for SqliteStore {
+    async fn add_page(&mut self, page: Page) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            "INSERT INTO pages (url, status, title, description, last_fetched, crawl_depth)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(url) DO UPDATE SET
+                status = excluded.status,
+                title = excluded.title,
+                description = excluded.description,
+                last_fetched = excluded.last_fetched,
+                crawl_depth = excluded.crawl_depth",
+        )
+        .bind(&page.url)
+        .bind(page.status as i64)
+        .bind(&page.title)
+        .bind(&page.description)
+        .bind(page.last_fetched.to_rfc3339())
+        .bind(page.crawl_depth as i64)
+        .execute(&self.pool)
+        .await?;
+
+        let cache_path = self.cache_path(&page.url);
+        let bytes = bincode::serialize(&page)?;
+        std::fs::write(cache_path, bytes)?;
+
+        Ok(())
+    }
+
+    async fn get_all_pages(&self) -> Result<Vec<Page>, Box<dyn Error + Send + Sync>> {
+        let rows = sqlx::query("SELECT url FROM pages").fetch_all(&self.pool).await?;
+
+        let mut pages = Vec::with_capacity(rows.len());
+        for row in rows {
+            let url: String = row.get("url");
+            if let Ok(bytes) = std::fs::read(self.cache_path(&url)) {
+                if let Ok(page) = bincode::deserialize::<Page>(&bytes) {
+                    pages.push(page);
+                }
+            }
+        }
+
+        Ok(pages)
+    }
+
+    async fn get_last_fetched(
+        &self,
+        url: &str,
+    ) -> Result<Option<DateTime<Utc>>, Box<dyn Error + Send + Sync>> {
+        let row = sqlx::query("SELECT last_fetched FROM pages WHERE url = ?1")
+            .bind(url)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row
+            .and_then(|row| row.try_get::<String, _>("last_fetched").ok())
+            .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+            .map(|dt| dt.with_timezone(&Utc)))
+    }
+}
@@ -0,0 +1,9 @@
+use url::Url;
+
+/// Normalizes a URL by stripping its fragment so equivalent links
+/// (e.g. `/page` and `/page#section`) collapse to a single queue entry.
+pub fn normalize_url(input: &str) -> Option<String> {
+    let mut parsed = Url::parse(input).ok()?;
+    parsed.set_fragment(None);
+    Some(parsed.to_string())
+}